@@ -17,7 +17,6 @@
 //! ## How does it work?
 //!
 //! TODO: document
-//! TODO: generate config.toml?
 //!
 //! ## Debugging
 //! One may want to set logging level to debug to see more details.
@@ -33,10 +32,13 @@ use html2md::parse_html;
 use log::*;
 use serde::Deserialize;
 use serde_xml_rs::from_reader;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs::create_dir_all;
 use std::fs::File;
 use std::env::args;
+use std::hash::{Hash, Hasher};
 use std::io::{Result, Write};
 use std::path::{Path, PathBuf};
 
@@ -47,10 +49,15 @@ const PAGINATE_BY: usize = 5;
 fn main() -> Result<()> {
     env_logger::init();
 
-    if let [input, output] = args().skip(1).take(2).collect::<Vec<_>>().as_slice() {
-        convert(input.into(), output.into())?;
+    let cli_args = args().skip(1).collect::<Vec<_>>();
+    let positional = cli_args.iter().filter(|a| !a.starts_with("--")).collect::<Vec<_>>();
+    let include_drafts = cli_args.iter().any(|a| a == "--drafts");
+    let download_media = cli_args.iter().any(|a| a == "--download-media");
+
+    if let [input, output] = positional.as_slice() {
+        convert((*input).into(), (*output).into(), include_drafts, download_media)?;
     } else {
-        eprintln!("Usage: wordpress-to-zola ./input.xml ./output-dir");
+        eprintln!("Usage: wordpress-to-zola ./input.xml ./output-dir [--drafts] [--download-media]");
     }
     Ok(())
 }
@@ -64,9 +71,60 @@ fn normalize_line_breaks(content: &str) -> String {
     normalized_content
 }
 
-/// Read xml from `input_file` and create `zola` content directory in
-/// `output_dir`.
-fn convert(input_file: PathBuf, output_dir: PathBuf) -> Result<()> {
+/// Find the wordpress excerpt marker `<!--more-->` (optionally with custom
+/// teaser text, e.g. `<!--more Read on-->`) and split the content there.
+/// Returns `None` if the content has no such marker.
+fn split_more(content: &str) -> Option<(&str, &str)> {
+    let mut search_from = 0;
+    while let Some(offset) = content[search_from..].find("<!--") {
+        let start = search_from + offset;
+        let tag_end = start + content[start..].find("-->")? + 3;
+        let inner = content[start..tag_end]
+            .trim_start_matches("<!--")
+            .trim_end_matches("-->")
+            .trim();
+        if inner.split_whitespace().next() == Some("more") {
+            return Some((&content[..start], &content[tag_end..]));
+        }
+        search_from = tag_end;
+    }
+    None
+}
+
+/// A post, fully rendered to markdown, ahead of knowing every other post
+/// or page's final path. `final_path` already reflects any media-bundle
+/// promotion, so it's what other posts' intra-site links should resolve
+/// to.
+struct PreparedPost {
+    title: String,
+    date: DateTime<FixedOffset>,
+    is_draft: bool,
+    categories: Vec<String>,
+    tags: Vec<String>,
+    aliases: Vec<String>,
+    markdown: String,
+    media_urls: Vec<String>,
+    section: PathBuf,
+    final_path: PathBuf,
+}
+
+/// A standalone page, fully rendered to markdown, ahead of knowing every
+/// other post or page's final path.
+struct PreparedPage {
+    title: String,
+    date: Option<DateTime<FixedOffset>>,
+    markdown: String,
+    final_path: PathBuf,
+}
+
+/// Read xml from `input_file` and create a zola project in `output_dir`:
+/// `config.toml` at its root and a `content` directory underneath holding
+/// every converted section and page. When `include_drafts` is set,
+/// `Status::Draft` posts are converted too, marked with `draft = true` in
+/// their front matter. When `download_media` is set, post images hosted on
+/// `base_site_url` are downloaded into a colocated page bundle instead of
+/// left as dead links.
+fn convert(input_file: PathBuf, output_dir: PathBuf, include_drafts: bool, download_media: bool) -> Result<()> {
     let file = File::open(input_file)?;
     let rss: Rss = from_reader(file).expect("cannot parse xml");
 
@@ -74,15 +132,21 @@ fn convert(input_file: PathBuf, output_dir: PathBuf) -> Result<()> {
     // nice filename for a post.
     let base_url = rss.channel.base_site_url;
 
-    // We will make `_index.md` for every top level section we will
-    // find. This set is used to only do that once per section.
-    let mut sections = HashSet::new();
+    // First pass: render every included post/page to markdown and decide
+    // its final path (including any media-bundle promotion), so that a
+    // second pass can rewrite intra-site links against paths that will
+    // really exist, and `config.toml` only declares taxonomies that occur.
+    let mut all_categories = HashSet::new();
+    let mut all_tags = HashSet::new();
+    let mut posts = Vec::new();
+    let mut pages = Vec::new();
 
     for item in rss.channel.item {
-        match item.status {
-            Status::Publish => {} // take only published posts
+        let is_draft = match item.status {
+            Status::Publish => false,
+            Status::Draft if include_drafts => true,
             _ => continue, // skip everything else
-        }
+        };
         match item.post_type {
             PostType::Post => {
                 let mut tags = Vec::new();
@@ -90,39 +154,129 @@ fn convert(input_file: PathBuf, output_dir: PathBuf) -> Result<()> {
 
                 for category in &item.categories {
                     match category.domain.as_str() {
-                        "post_tag" => tags.push(category.nicename.clone()),
-                        "category" => categories.push(category.nicename.clone()),
+                        "post_tag" => {
+                            all_tags.insert(category.nicename.clone());
+                            tags.push(category.nicename.clone());
+                        },
+                        "category" => {
+                            all_categories.insert(category.nicename.clone());
+                            categories.push(category.nicename.clone());
+                        },
                         _ => {}
                     }
                 }
 
-                let path = output_dir.join(generate_path(&base_url, &item.link));
-                info!("Post [{:?}] {} -> {:?}", item.status, item.title, &path);
-
-                let section = path.parent().expect("no parent in filename");
-                // ensure all directories are in place
-                debug!("Creating directory {:?}", section);
-                create_dir_all(&path.parent().expect("no parent in filename"))?;
-
-                // if it's the first time we see this section, create section file
-                if sections.insert(section.to_owned()) {
-                    create_section(section)?;
-                }
-
                 let date = DateTime::parse_from_rfc2822(&item.pub_date)
                     .expect("cannot parse pubDate");
 
                 let raw_content = item.content();
-                let normalized_content = normalize_line_breaks(raw_content);  
-                let markdown = parse_html(&normalized_content); 
-                debug!("{}", markdown);
+                let markdown = match split_more(raw_content) {
+                    Some((before, after)) => {
+                        let before_md = parse_html(&normalize_line_breaks(before));
+                        let after_md = parse_html(&normalize_line_breaks(after));
+                        format!("{}\n\n<!-- more -->\n\n{}", before_md.trim_end(), after_md.trim_start())
+                    },
+                    None => parse_html(&normalize_line_breaks(raw_content)),
+                };
+
+                let post_path = generate_path(&base_url, &item.link);
+                let section = post_path.parent().expect("no parent in filename").to_owned();
+                let media_urls = if download_media { find_media_urls(&base_url, &markdown) } else { Vec::new() };
+                // A post with media gets promoted into a colocated-assets
+                // bundle so the downloaded media can live next to it.
+                let final_path = if media_urls.is_empty() {
+                    post_path
+                } else {
+                    post_path.with_extension("").join("index.md")
+                };
+
+                let aliases = compute_aliases(&base_url, &item.link, item.guid.as_deref());
 
-                create_page(&path, &item.title, date, &markdown, &categories, &tags)?;
+                posts.push(PreparedPost {
+                    title: item.title,
+                    date,
+                    is_draft,
+                    categories,
+                    tags,
+                    aliases,
+                    markdown,
+                    media_urls,
+                    section,
+                    final_path,
+                });
+            },
+            PostType::Page => {
+                // WordPress pages (About, Contact, ...) are not part of
+                // the dated, taxonomy-driven blog sections: they get a
+                // plain page bundle instead.
+                let date = DateTime::parse_from_rfc2822(&item.pub_date).ok();
+                let markdown = parse_html(&normalize_line_breaks(item.content()));
+                let final_path = generate_page_path(&base_url, &item.link);
 
+                pages.push(PreparedPage {
+                    title: item.title,
+                    date,
+                    markdown,
+                    final_path,
+                });
             },
-            _ => debug!("Ignoring attachment {}", item.title),
+            PostType::Attachment => debug!("Ignoring attachment {}", item.title),
+        }
+    }
+
+    // Every post/page's real final path is now known, including bundle
+    // promotion, so intra-site links can be rewritten safely.
+    let known_paths: HashSet<PathBuf> = posts.iter().map(|post| post.final_path.clone())
+        .chain(pages.iter().map(|page| page.final_path.clone()))
+        .collect();
+
+    // `output_dir` is the project root; zola expects `config.toml` there
+    // and every converted section/page underneath a `content` directory.
+    let content_dir = output_dir.join("content");
+    create_dir_all(&content_dir)?;
+    create_config(&output_dir, &base_url, &rss.channel.title, &all_categories, &all_tags)?;
+
+    // We will make `_index.md` for every top level section we will
+    // find. This set is used to only do that once per section.
+    let mut sections = HashSet::new();
+
+    // Downloaded media, keyed by source url, so a shared image is only
+    // fetched over the network once.
+    let mut media_cache = HashMap::new();
+
+    for post in posts {
+        let section = content_dir.join(&post.section);
+        create_dir_all(&section)?;
+        if sections.insert(section.clone()) {
+            create_section(&section)?;
+        }
+
+        let path = content_dir.join(&post.final_path);
+        info!("Post -> {:?}", &path);
+        create_dir_all(path.parent().expect("no parent in filename"))?;
+
+        let mut markdown = post.markdown;
+        let mut used_filenames = HashSet::new();
+        for url in &post.media_urls {
+            let (bytes, content_type) = fetch_media(url, &mut media_cache);
+            let filename = dedupe_filename(media_filename(url, content_type.as_deref()), &mut used_filenames);
+            File::create(path.parent().unwrap().join(&filename))?.write_all(&bytes)?;
+            markdown = markdown.replace(url.as_str(), &filename);
         }
+        markdown = rewrite_internal_links(&base_url, &markdown, &known_paths);
+        debug!("{}", markdown);
+
+        create_page(&path, &post.title, post.date, &markdown, &post.categories, &post.tags, post.is_draft, &post.aliases)?;
+    }
+
+    for page in pages {
+        let path = content_dir.join(&page.final_path);
+        info!("Page -> {:?}", &path);
+        create_dir_all(path.parent().expect("no parent in filename"))?;
+        debug!("{}", page.markdown);
+        create_standalone_page(&path, &page.title, page.date, &page.markdown)?;
     }
+
     Ok(())
 }
 
@@ -136,6 +290,7 @@ struct Rss {
 /// Main wrapper
 #[derive(Debug, Deserialize)]
 struct Channel {
+    title: String,
     base_site_url: String,
     item: Vec<Item>,
 }
@@ -150,6 +305,8 @@ struct Item {
     post_type: PostType,
     encoded: Vec<String>,
     status: Status,
+    #[serde(default)]
+    guid: Option<String>,
     #[serde(rename = "category", default)]
     categories: Vec<Category>,
 }
@@ -176,6 +333,7 @@ impl Item {
 enum PostType {
     Attachment,
     Post,
+    Page,
 }
 
 #[derive(Debug, Deserialize)]
@@ -187,6 +345,37 @@ enum Status {
     Private,
 }
 
+/// Create top-level `config.toml` describing the zola site.
+///
+/// `categories` and `tags` are the full set of distinct nicenames found
+/// across all converted items, so only taxonomies that are actually used
+/// get declared.
+fn create_config(output_dir: &Path, base_url: &str, title: &str, categories: &HashSet<String>, tags: &HashSet<String>) -> Result<()> {
+    let mut file = File::create(output_dir.join("config.toml"))?;
+    let escaped_title = title.replace("\"", "\\\"");
+    writeln!(file, "base_url = \"{}\"", base_url)?;
+    writeln!(file, "title = \"{}\"", escaped_title)?;
+    writeln!(file, "generate_feeds = true")?;
+    writeln!(file, "feed_filenames = [\"atom.xml\"]")?;
+
+    if !categories.is_empty() || !tags.is_empty() {
+        writeln!(file)?;
+        if !categories.is_empty() {
+            writeln!(file, "[[taxonomies]]")?;
+            writeln!(file, "name = \"categories\"")?;
+            writeln!(file, "feed = true")?;
+            writeln!(file, "paginate_by = {}", PAGINATE_BY)?;
+        }
+        if !tags.is_empty() {
+            writeln!(file, "[[taxonomies]]")?;
+            writeln!(file, "name = \"tags\"")?;
+            writeln!(file, "feed = true")?;
+            writeln!(file, "paginate_by = {}", PAGINATE_BY)?;
+        }
+    }
+    Ok(())
+}
+
 /// Create section `_index.md` file.
 fn create_section(section: &Path) -> Result<()> {
     let mut file = File::create(section.join("_index.md"))?;
@@ -199,13 +388,20 @@ fn create_section(section: &Path) -> Result<()> {
 }
 
 /// Create post file
-fn create_page(path: &Path, title: &str, date: DateTime<FixedOffset>, markdown: &str, categories: &[String], tags: &[String]) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+fn create_page(path: &Path, title: &str, date: DateTime<FixedOffset>, markdown: &str, categories: &[String], tags: &[String], is_draft: bool, aliases: &[String]) -> Result<()> {
     let mut file = File::create(path)?;
     let escaped_title = title.replace("\"", "\\\"");
     // write front-matter
     writeln!(file, "+++")?;
     writeln!(file, "title = \"{}\"", escaped_title)?;
     writeln!(file, "date = {}", date.to_rfc3339())?;
+    if is_draft {
+        writeln!(file, "draft = true")?;
+    }
+    if !aliases.is_empty() {
+        writeln!(file, "aliases = [{}]", aliases.iter().map(|a| format!("\"{}\"", a)).collect::<Vec<_>>().join(", "))?;
+    }
     writeln!(file, "[taxonomies]")?;
     writeln!(file, "categories = [{}]", categories.iter().map(|c| format!("\"{}\"", c)).collect::<Vec<_>>().join(", "))?;
     writeln!(file, "tags = [{}]", tags.iter().map(|t| format!("\"{}\"", t)).collect::<Vec<_>>().join(", "))?;
@@ -215,6 +411,198 @@ fn create_page(path: &Path, title: &str, date: DateTime<FixedOffset>, markdown:
     Ok(())
 }
 
+/// Create a standalone page file, for WordPress `page`s rather than blog
+/// posts: no taxonomies, and `date` is omitted when the page has none.
+fn create_standalone_page(path: &Path, title: &str, date: Option<DateTime<FixedOffset>>, markdown: &str) -> Result<()> {
+    let mut file = File::create(path)?;
+    let escaped_title = title.replace("\"", "\\\"");
+    writeln!(file, "+++")?;
+    writeln!(file, "title = \"{}\"", escaped_title)?;
+    if let Some(date) = date {
+        writeln!(file, "date = {}", date.to_rfc3339())?;
+    }
+    writeln!(file, "template = \"page.html\"")?;
+    writeln!(file, "+++")?;
+    writeln!(file, "{}", markdown)?;
+    Ok(())
+}
+
+/// Resolve `url` against `known_paths`, trying every shape a post or page
+/// link to the same wordpress site could have produced (a plain
+/// `<slug>.md` post, or a `<slug>/index.md` page or media-bundled post),
+/// returning the matching relative path if one will actually exist.
+fn resolve_known_path(base_url: &str, url: &str, known_paths: &HashSet<PathBuf>) -> Option<String> {
+    let slug = url.strip_prefix(base_url)?.trim_matches('/');
+    vec![format!("{}.md", slug), format!("{}/index.md", slug)]
+        .into_iter()
+        .find(|candidate| known_paths.contains(&PathBuf::from(candidate)))
+}
+
+/// Rewrite markdown links (`[text](url)`, but not `![alt](url)` images)
+/// that point at another page on the same wordpress site into zola's
+/// internal `@/` link syntax, so they keep resolving after the site's
+/// `base_url` changes and zola validates them at build time. Only links
+/// whose target is in `known_paths` — i.e. will actually be produced by
+/// this conversion, including media-bundle promotion — are rewritten.
+fn rewrite_internal_links(base_url: &str, markdown: &str, known_paths: &HashSet<PathBuf>) -> String {
+    let mut result = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+    while let Some(paren_start) = rest.find("](") {
+        let bracket_start = match rest[..paren_start].rfind('[') {
+            Some(i) => i,
+            None => {
+                result.push_str(&rest[..paren_start + 2]);
+                rest = &rest[paren_start + 2..];
+                continue;
+            },
+        };
+        let is_image = bracket_start > 0 && rest.as_bytes()[bracket_start - 1] == b'!';
+        let url_start = paren_start + 2;
+        let url_end = match rest[url_start..].find(')') {
+            Some(i) => url_start + i,
+            None => {
+                result.push_str(rest);
+                rest = "";
+                break;
+            },
+        };
+        let url = &rest[url_start..url_end];
+        match resolve_known_path(base_url, url, known_paths) {
+            Some(resolved) if !is_image => {
+                result.push_str(&rest[..paren_start + 2]);
+                result.push_str("@/");
+                result.push_str(&resolved);
+            },
+            _ => result.push_str(&rest[..url_end]),
+        }
+        result.push(')');
+        rest = &rest[url_end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Find every distinct markdown image URL (`![alt](url)`) hosted under
+/// `base_url`, in the order they first occur.
+///
+/// This only looks at image syntax, not plain links (`[text](url)`), so a
+/// link to an on-site media file that isn't rendered as an image (e.g. a
+/// PDF, or a link to the full-size original behind a thumbnail) is left
+/// as a dead absolute link even with `--download-media`.
+fn find_media_urls(base_url: &str, markdown: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut rest = markdown;
+    while let Some(start) = rest.find("![") {
+        rest = &rest[start..];
+        let url = rest.find('(').and_then(|paren_start| {
+            rest[paren_start..].find(')').map(|paren_end| &rest[paren_start + 1..paren_start + paren_end])
+        });
+        match url {
+            Some(url) if url.starts_with(base_url) && !urls.iter().any(|u| u == url) => {
+                urls.push(url.to_owned());
+            }
+            _ => {}
+        }
+        rest = &rest[2..];
+    }
+    urls
+}
+
+/// Derive an asset filename from the last path segment of a media URL,
+/// stripping any query string (e.g. a `?resize=300,200` appended by some
+/// wordpress themes) so it doesn't end up baked into the saved filename.
+/// When the resulting name has no extension, one is guessed from
+/// `content_type` so the asset still opens correctly (zola and browsers
+/// both rely on the extension, not just file contents).
+fn media_filename(url: &str, content_type: Option<&str>) -> String {
+    let without_query = url.split('?').next().unwrap_or(url);
+    let basename = without_query.rsplit('/').next().filter(|s| !s.is_empty()).unwrap_or("file");
+    if Path::new(basename).extension().is_some() {
+        return basename.to_owned();
+    }
+    let extension = match content_type.map(|ct| ct.split(';').next().unwrap_or(ct).trim()) {
+        Some("image/jpeg") => "jpg",
+        Some("image/png") => "png",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some("image/svg+xml") => "svg",
+        Some("application/pdf") => "pdf",
+        _ => return basename.to_owned(),
+    };
+    format!("{}.{}", basename, extension)
+}
+
+/// Disambiguate `filename` against `used` (the filenames already claimed
+/// in the same bundle), so that two distinct media URLs which happen to
+/// share a last path segment (e.g. same-named uploads from different
+/// months) don't silently overwrite each other. Returns `filename`
+/// unchanged the first time it's seen.
+fn dedupe_filename(filename: String, used: &mut HashSet<String>) -> String {
+    if used.insert(filename.clone()) {
+        return filename;
+    }
+    let mut hasher = DefaultHasher::new();
+    filename.hash(&mut hasher);
+    loop {
+        let unique = match filename.split_once('.') {
+            Some((stem, extension)) => format!("{}-{:x}.{}", stem, hasher.finish(), extension),
+            None => format!("{}-{:x}", filename, hasher.finish()),
+        };
+        if used.insert(unique.clone()) {
+            return unique;
+        }
+        unique.hash(&mut hasher);
+    }
+}
+
+/// Download the bytes at `url`, reusing an earlier download from `cache`
+/// when the same URL was already fetched (e.g. an image shared by several
+/// posts). Returns the response's `Content-Type`, if any, alongside the
+/// bytes so a missing file extension can be recovered.
+fn fetch_media(url: &str, cache: &mut HashMap<String, (Vec<u8>, Option<String>)>) -> (Vec<u8>, Option<String>) {
+    if let Some(cached) = cache.get(url) {
+        return cached.clone();
+    }
+    debug!("Downloading media {}", url);
+    let result = reqwest::blocking::get(url)
+        .and_then(|response| {
+            let content_type = response.headers().get(reqwest::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_owned());
+            response.bytes().map(|bytes| (bytes.to_vec(), content_type))
+        })
+        .unwrap_or_else(|err| {
+            warn!("Failed to download {}: {}", url, err);
+            (Vec::new(), None)
+        });
+    cache.insert(url.to_owned(), result.clone());
+    result
+}
+
+/// Derive the original WordPress permalink paths for a post's `aliases`,
+/// from its `link` and, if present, a distinct `guid` (e.g. a stable
+/// `?p=123` permalink). Each alias is relative to `base_url` and
+/// normalized to end with a slash, except query-string permalinks which
+/// are kept as-is.
+fn compute_aliases(base_url: &str, link: &str, guid: Option<&str>) -> Vec<String> {
+    let mut aliases = Vec::new();
+    for url in std::iter::once(link).chain(guid) {
+        let path = url.trim_start_matches(base_url);
+        if !path.starts_with('/') {
+            continue;
+        }
+        let alias = if path.ends_with('/') || path.contains('?') {
+            path.to_owned()
+        } else {
+            format!("{}/", path)
+        };
+        if !aliases.contains(&alias) {
+            aliases.push(alias);
+        }
+    }
+    aliases
+}
+
 /// Generate path for an item by splicing base url from the link.
 fn generate_path(base_url: &str, link: &str) -> PathBuf {
     PathBuf::from(format!(
@@ -222,3 +610,77 @@ fn generate_path(base_url: &str, link: &str) -> PathBuf {
         link.trim_start_matches(&base_url).trim_matches('/')
     ))
 }
+
+/// Generate path for a standalone page, as an `index.md` bundle at its
+/// link's path (e.g. `about/index.md`, or `about/team/index.md` for a
+/// nested page) rather than a dated post file.
+fn generate_page_path(base_url: &str, link: &str) -> PathBuf {
+    let slug = link.trim_start_matches(base_url).trim_matches('/');
+    PathBuf::from(slug).join("index.md")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_more_finds_plain_marker() {
+        let content = "before<!--more-->after";
+        assert_eq!(split_more(content), Some(("before", "after")));
+    }
+
+    #[test]
+    fn split_more_finds_marker_with_teaser_text() {
+        let content = "before<!--more Read on-->after";
+        assert_eq!(split_more(content), Some(("before", "after")));
+    }
+
+    #[test]
+    fn split_more_skips_unrelated_comments() {
+        let content = "before<!-- wp:paragraph -->middle<!--more-->after";
+        assert_eq!(split_more(content), Some(("before<!-- wp:paragraph -->middle", "after")));
+    }
+
+    #[test]
+    fn split_more_returns_none_without_marker() {
+        let content = "before<!-- wp:paragraph -->after";
+        assert_eq!(split_more(content), None);
+    }
+
+    #[test]
+    fn split_more_returns_none_on_unterminated_comment() {
+        let content = "before<!--more";
+        assert_eq!(split_more(content), None);
+    }
+
+    #[test]
+    fn resolve_known_path_finds_plain_post_shape() {
+        let mut known_paths = HashSet::new();
+        known_paths.insert(PathBuf::from("2020/01/hello-world.md"));
+        let resolved = resolve_known_path("http://oldsite.com", "http://oldsite.com/2020/01/hello-world/", &known_paths);
+        assert_eq!(resolved, Some("2020/01/hello-world.md".to_owned()));
+    }
+
+    #[test]
+    fn resolve_known_path_finds_bundle_or_page_shape() {
+        let mut known_paths = HashSet::new();
+        known_paths.insert(PathBuf::from("about/index.md"));
+        let resolved = resolve_known_path("http://oldsite.com", "http://oldsite.com/about/", &known_paths);
+        assert_eq!(resolved, Some("about/index.md".to_owned()));
+    }
+
+    #[test]
+    fn resolve_known_path_returns_none_for_offsite_url() {
+        let mut known_paths = HashSet::new();
+        known_paths.insert(PathBuf::from("about/index.md"));
+        let resolved = resolve_known_path("http://oldsite.com", "http://othersite.com/about/", &known_paths);
+        assert_eq!(resolved, None);
+    }
+
+    #[test]
+    fn resolve_known_path_returns_none_when_not_produced() {
+        let known_paths = HashSet::new();
+        let resolved = resolve_known_path("http://oldsite.com", "http://oldsite.com/about/", &known_paths);
+        assert_eq!(resolved, None);
+    }
+}